@@ -0,0 +1,80 @@
+//
+// Copyright 2023 Jacob R. Green
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! A mutex the owning thread can re-enter, used to serialize [`Property`]/
+//! [`Event`] callback dispatch.
+//!
+//! [`Property`](crate::property::Property) and [`Event`](crate::event::Event)
+//! snapshot their callback list and release their data lock before running
+//! any callback, so that a callback may itself read, write, or invoke the
+//! same property/event without deadlocking. But releasing the data lock
+//! first also means nothing stops two threads from committing two separate
+//! writes and then running their dispatch loops at the same time, each
+//! invoking the same `Arc<UnsafeCell<dyn FnMut>>` callback concurrently —
+//! undefined behavior, since the callback has no synchronization of its
+//! own. [`DispatchLock`] closes that gap: dispatch loops across threads are
+//! serialized through it, while the thread already running one may still
+//! re-enter it, so a callback that triggers a reentrant dispatch on its own
+//! thread doesn't deadlock against itself.
+
+use std::sync::{Condvar, Mutex};
+use std::thread::{self, ThreadId};
+
+#[derive(Debug, Default)]
+struct State {
+    owner: Option<ThreadId>,
+    depth: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct DispatchLock {
+    state: Mutex<State>,
+    released: Condvar,
+}
+
+impl DispatchLock {
+    /// Enters the lock, blocking until no other thread is dispatching.
+    /// The calling thread may call this again before releasing its first
+    /// guard without blocking on itself.
+    pub(crate) fn enter(&self) -> DispatchGuard<'_> {
+        let this_thread = thread::current().id();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(owner) = state.owner {
+            if owner == this_thread {
+                break;
+            }
+            state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.owner = Some(this_thread);
+        state.depth += 1;
+        DispatchGuard { lock: self }
+    }
+}
+
+pub(crate) struct DispatchGuard<'a> {
+    lock: &'a DispatchLock,
+}
+
+impl Drop for DispatchGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.lock.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.depth -= 1;
+        if state.depth == 0 {
+            state.owner = None;
+            self.lock.released.notify_one();
+        }
+    }
+}
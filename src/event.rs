@@ -14,51 +14,132 @@
 // limitations under the License.
 //
 
-use std::cell::UnsafeCell;
-use std::sync::{Arc, Mutex, Weak};
+use crate::dispatch::DispatchLock;
+use crate::lock::RawMutex;
+#[cfg(all(feature = "spin", not(feature = "std")))]
+use crate::lock::SpinMutex;
+#[cfg(feature = "std")]
+use crate::lock::StdMutex;
+use crate::panic::PanicPolicy;
 use crate::Leak;
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, TryLockError, Weak};
 
+type Callback<Args> = Arc<UnsafeCell<dyn FnMut(&Args)>>;
+type WeakCallback<Args> = Weak<UnsafeCell<dyn FnMut(&Args)>>;
+
+// `pub` for the same reason as `property::PropertyData`: it appears in
+// the trait bounds of public types (`Event`, `Hook`) via
+// `M: RawMutex<EventInner<Args>>`. Its fields stay private.
 #[derive(Debug, Default)]
-struct EventInner<Args> {
-    callbacks: Vec<Arc<UnsafeCell<dyn FnMut(&Args) -> ()>>>,
+pub struct EventInner<Args> {
+    callbacks: Vec<Callback<Args>>,
+    panic_policy: PanicPolicy,
+}
+
+impl<Args> EventInner<Args> {
+    /// Snapshots the callback list so it can be dispatched after the mutex
+    /// guarding this data has been released, instead of while it's still
+    /// held.
+    fn snapshot(&self) -> EventDispatch<Args> {
+        EventDispatch {
+            callbacks: self.callbacks.clone(),
+            panic_policy: self.panic_policy.clone(),
+        }
+    }
+}
+
+/// A snapshot of an event's callback list, dispatched once the event's
+/// lock has been released so a callback may safely hook, unhook, or invoke
+/// the same event without deadlocking.
+struct EventDispatch<Args> {
+    callbacks: Vec<Callback<Args>>,
+    panic_policy: PanicPolicy,
+}
+
+impl<Args> EventDispatch<Args> {
+    fn run(self, args: &Args) {
+        let mut panics = Vec::new();
+        for callback in &self.callbacks {
+            let panic = self
+                .panic_policy
+                .invoke(|| unsafe { (*callback.get())(args) });
+            panics.extend(panic);
+        }
+        self.panic_policy.finish(panics);
+    }
 }
 
 /// A thread-safe event that can be hooked into.
 ///
+/// Generic over the lock backend `M` guarding its callback list. The `std`
+/// feature (on by default) selects [`StdMutex`], matching the original
+/// `std::sync`-backed behavior; with `std` disabled, the `spin` feature
+/// selects a spin-based backend instead (see [`crate::lock`] for why this
+/// doesn't make the crate `no_std`). Existing code that names `Event<Args>`
+/// keeps compiling unchanged against whichever backend feature is active.
+///
 /// # Example
 /// ```rust
 /// use eventify::event::*;
 ///
 /// fn main() {
-///     let event = Event::new();
+///     let event = Event::<i32>::new();
 ///     let hook = event.hook(|args: &i32| {
 ///         println!("Event fired with args: {}", args);
 ///     });
 ///     event.invoke(&42);
 /// }
 /// ```
-#[derive(Debug, Default)]
-pub struct Event<Args = ()> {
-    inner: Arc<Mutex<EventInner<Args>>>,
+#[derive(Debug)]
+pub struct Event<
+    #[cfg(feature = "std")] Args = (),
+    #[cfg(not(feature = "std"))] Args,
+    #[cfg(feature = "std")] M: RawMutex<EventInner<Args>> = StdMutex<EventInner<Args>>,
+    #[cfg(all(feature = "spin", not(feature = "std")))] M: RawMutex<EventInner<Args>> =
+        SpinMutex<EventInner<Args>>,
+    #[cfg(not(any(feature = "std", feature = "spin")))] M: RawMutex<EventInner<Args>>,
+> {
+    inner: Arc<M>,
+    dispatch_lock: Arc<DispatchLock>,
+    _marker: PhantomData<Args>,
 }
 
-impl<Args> Event<Args> {
+impl<Args, M: RawMutex<EventInner<Args>>> Default for Event<Args, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Args, M: RawMutex<EventInner<Args>>> Event<Args, M> {
     pub fn new() -> Self {
         Self {
-            inner: Arc::new(Mutex::new(EventInner {
+            inner: Arc::new(M::new(EventInner {
                 callbacks: Vec::new(),
+                panic_policy: Default::default(),
             })),
+            dispatch_lock: Arc::new(DispatchLock::default()),
+            _marker: PhantomData,
         }
     }
 
+    /// Sets how panicking callbacks are handled when the event is invoked.
+    ///
+    /// Defaults to [`PanicPolicy::SwallowAndLog`].
+    pub fn set_panic_policy(&self, policy: PanicPolicy) {
+        self.inner.lock().unwrap().panic_policy = policy;
+    }
+
     /// Hooks a callback into the event, returning a hook that can be used to remove the hook.
     #[must_use]
     #[inline(always)]
-    pub fn hook(&self, callback: impl FnMut(&Args) + 'static) -> Hook<Args> {
+    pub fn hook(&self, callback: impl FnMut(&Args) + 'static) -> Hook<Args, M> {
         self.hook_internal(Arc::new(UnsafeCell::new(callback)))
     }
 
-    fn hook_internal(&self, callback: Arc<UnsafeCell<dyn FnMut(&Args) -> ()>>) -> Hook<Args> {
+    fn hook_internal(&self, callback: Callback<Args>) -> Hook<Args, M> {
         let mut inner = self.inner.lock().unwrap();
 
         let weak_inner = Arc::downgrade(&self.inner);
@@ -74,20 +155,103 @@ impl<Args> Event<Args> {
     }
 
     /// Invokes the event, calling all hooked callbacks.
+    ///
+    /// The callback list is snapshotted and the lock released before any
+    /// callback runs, so a callback may safely hook, unhook, or invoke this
+    /// same event without deadlocking (`std::sync::Mutex` does not support
+    /// recursive acquisition). A hook removed concurrently with an
+    /// in-flight invocation may still observe one final call, since it was
+    /// already part of the dispatched snapshot.
+    ///
+    /// Dispatch itself is serialized through a [`DispatchLock`], entered
+    /// after the data lock is released: otherwise two invocations on
+    /// separate threads could release the data lock and run their dispatch
+    /// loops at the same time, each invoking the same callback concurrently.
     pub fn invoke(&self, args: &Args) {
-        let inner = self.inner.lock().unwrap();
-        for callback in &inner.callbacks {
-            unsafe {
-                (*callback.get())(args);
-            }
+        let dispatch = self.inner.lock().unwrap().snapshot();
+        let _guard = self.dispatch_lock.enter();
+        dispatch.run(args);
+    }
+
+    /// Hooks a callback into the event without blocking, returning a hook
+    /// that can be used to remove the hook.
+    ///
+    /// This is useful for real-time or UI threads that must never stall
+    /// behind a slow invocation.
+    #[inline(always)]
+    pub fn try_hook(
+        &self,
+        callback: impl FnMut(&Args) + 'static,
+    ) -> Result<Hook<Args, M>, TryInvokeError> {
+        self.try_hook_internal(Arc::new(UnsafeCell::new(callback)))
+    }
+
+    fn try_hook_internal(&self, callback: Callback<Args>) -> Result<Hook<Args, M>, TryInvokeError> {
+        let mut inner = self.inner.try_lock().map_err(TryInvokeError::from)?;
+
+        let weak_inner = Arc::downgrade(&self.inner);
+        let weak_callback = Arc::downgrade(&callback);
+
+        inner.callbacks.push(callback);
+        Ok(Hook {
+            data: Some(EventHookData {
+                inner: weak_inner,
+                callback: weak_callback,
+            }),
+        })
+    }
+
+    /// Invokes the event without blocking, calling all hooked callbacks.
+    ///
+    /// Returns [`TryInvokeError::WouldBlock`] rather than waiting if the
+    /// event's callback list is currently locked. Only that lock is tried
+    /// without blocking: once the callback list has been snapshotted, this
+    /// still waits on the [`DispatchLock`] for an invocation already running
+    /// its callbacks on another thread to finish.
+    pub fn try_invoke(&self, args: &Args) -> Result<(), TryInvokeError> {
+        let inner = self.inner.try_lock().map_err(TryInvokeError::from)?;
+        let dispatch = inner.snapshot();
+        drop(inner);
+        let _guard = self.dispatch_lock.enter();
+        dispatch.run(args);
+        Ok(())
+    }
+}
+
+/// Error returned when an [`Event`] could not be locked without blocking.
+#[derive(Debug)]
+pub enum TryInvokeError {
+    /// The event's lock was poisoned by a thread that panicked while
+    /// holding it.
+    Poisoned,
+    /// The event's lock is currently held by another thread.
+    WouldBlock,
+}
+
+impl<T> From<TryLockError<T>> for TryInvokeError {
+    fn from(e: TryLockError<T>) -> Self {
+        match e {
+            TryLockError::Poisoned(_) => TryInvokeError::Poisoned,
+            TryLockError::WouldBlock => TryInvokeError::WouldBlock,
+        }
+    }
+}
+
+impl fmt::Display for TryInvokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryInvokeError::Poisoned => write!(f, "event lock poisoned"),
+            TryInvokeError::WouldBlock => write!(f, "event would block"),
         }
     }
 }
 
+impl std::error::Error for TryInvokeError {}
+
 #[derive(Debug)]
-struct EventHookData<Args> {
-    inner: Weak<Mutex<EventInner<Args>>>,
-    callback: Weak<UnsafeCell<dyn FnMut(&Args)>>,
+struct EventHookData<Args, M: RawMutex<EventInner<Args>>> {
+    inner: Weak<M>,
+    callback: WeakCallback<Args>,
 }
 
 /// A hook into an event.
@@ -95,11 +259,11 @@ struct EventHookData<Args> {
 /// Hooks can be dropped to remove it from the event or
 /// leaked to keep it alive till the event is dropped.
 #[derive(Debug)]
-pub struct Hook<Args> {
-    data: Option<EventHookData<Args>>,
+pub struct Hook<Args, M: RawMutex<EventInner<Args>>> {
+    data: Option<EventHookData<Args, M>>,
 }
 
-impl<Args> Hook<Args> {
+impl<Args, M: RawMutex<EventInner<Args>>> Hook<Args, M> {
     /// Returns true if the event is still alive.
     pub fn is_alive(&self) -> bool {
         self.data
@@ -115,26 +279,79 @@ impl<Args> Hook<Args> {
     }
 }
 
-impl<Args> Leak for Hook<Args> {
+impl<Args, M: RawMutex<EventInner<Args>>> Leak for Hook<Args, M> {
     fn leak(mut self) {
         self.data.take();
     }
 }
 
-impl<Args> Drop for Hook<Args> {
+impl<Args, M: RawMutex<EventInner<Args>>> Drop for Hook<Args, M> {
     fn drop(&mut self) {
-        self.data.as_ref().map(
-            |EventHookData {
-                 inner,
-                 callback: handler,
-             }| {
-                inner.upgrade().map(|inner| {
-                    let mut inner = inner.lock().unwrap();
-                    handler.upgrade().map(|handler| {
-                        inner.callbacks.retain(|h| !Arc::ptr_eq(h, &handler));
-                    });
-                });
-            },
-        );
+        let Some(EventHookData {
+            inner,
+            callback: handler,
+        }) = self.data.as_ref()
+        else {
+            return;
+        };
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        let Some(handler) = handler.upgrade() else {
+            return;
+        };
+        let mut inner = inner.lock().unwrap();
+        inner.callbacks.retain(|h| !Arc::ptr_eq(h, &handler));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_hook_and_try_invoke_return_would_block_while_locked() {
+        let event = Event::<i32>::new();
+        let _guard = event.inner.lock().unwrap();
+
+        assert!(matches!(
+            event.try_hook(|_: &i32| {}),
+            Err(TryInvokeError::WouldBlock)
+        ));
+        assert!(matches!(
+            event.try_invoke(&0),
+            Err(TryInvokeError::WouldBlock)
+        ));
+    }
+
+    #[test]
+    fn panicking_callback_does_not_poison_subsequent_invokes() {
+        let event = Event::<i32>::new();
+        let _hook = event.hook(|_| panic!("callback panic"));
+
+        event.invoke(&1);
+
+        assert!(event.try_invoke(&2).is_ok());
+    }
+
+    #[test]
+    fn reentrant_invoke_from_callback_does_not_deadlock() {
+        // `Rc`, not `Arc`: matches `Property`'s equivalent test — this only
+        // needs same-thread reentrancy, never cross-thread sharing.
+        let event = std::rc::Rc::new(Event::<i32>::new());
+        let count = std::rc::Rc::new(std::cell::Cell::new(0));
+
+        let reentrant_event = event.clone();
+        let reentrant_count = count.clone();
+        let _hook = event.hook(move |value| {
+            reentrant_count.set(reentrant_count.get() + 1);
+            if *value < 3 {
+                reentrant_event.invoke(&(value + 1));
+            }
+        });
+
+        event.invoke(&1);
+
+        assert_eq!(count.get(), 3);
     }
 }
@@ -0,0 +1,29 @@
+//
+// Copyright 2023 Jacob R. Green
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Thread-safe properties and events with deferred changed-event dispatch.
+
+mod dispatch;
+pub mod event;
+pub mod lock;
+pub mod panic;
+pub mod property;
+
+/// Types that can be leaked, keeping their effect alive for as long as the
+/// thing they're attached to, instead of being torn down when dropped.
+pub trait Leak {
+    fn leak(self);
+}
@@ -0,0 +1,210 @@
+//
+// Copyright 2023 Jacob R. Green
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Pluggable lock backends for [`Property`](crate::property::Property) and
+//! [`Event`](crate::event::Event).
+//!
+//! [`Property`](crate::property::Property) and [`Event`](crate::event::Event)
+//! are generic over the [`RawRwLock`] and [`RawMutex`] traits defined here so
+//! the same public API can sit on top of different lock implementations.
+//! The `std` feature (on by default) selects [`StdRwLock`]/[`StdMutex`],
+//! which are thin wrappers around `std::sync`. With `std` disabled, the
+//! `spin` feature selects [`SpinRwLock`]/[`SpinMutex`] instead, which spin
+//! rather than block and never poison. Both default type parameters
+//! preserve the existing `std::sync`-backed behavior, so `Property<T>`/
+//! `Event<Args>` keep working
+//! exactly as before.
+//!
+//! Note that swapping in the `spin` backend does not make the crate
+//! `no_std`: callback dispatch ([`crate::dispatch::DispatchLock`]) and panic
+//! isolation ([`crate::panic::PanicPolicy`]) still depend on `std::sync`,
+//! `std::thread`, and `std::panic::catch_unwind` unconditionally. `spin` is
+//! only useful today for code that wants to avoid OS-level blocking/
+//! poisoning on the data lock itself while still linking `std`.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{LockResult, TryLockResult};
+
+/// A raw read-write lock that can wrap an arbitrary value.
+///
+/// Mirrors the shape of `std::sync::RwLock`, so a backend that never
+/// poisons (e.g. a spinlock) can still report success/failure through the
+/// same [`LockResult`]/[`TryLockResult`] types.
+pub trait RawRwLock<T>: Sized {
+    type ReadGuard<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+    type WriteGuard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self;
+
+    fn read(&self) -> LockResult<Self::ReadGuard<'_>>;
+    fn write(&self) -> LockResult<Self::WriteGuard<'_>>;
+
+    fn try_read(&self) -> TryLockResult<Self::ReadGuard<'_>>;
+    fn try_write(&self) -> TryLockResult<Self::WriteGuard<'_>>;
+}
+
+/// A raw mutex that can wrap an arbitrary value.
+///
+/// Mirrors the shape of `std::sync::Mutex`, for the same reason as
+/// [`RawRwLock`].
+pub trait RawMutex<T>: Sized {
+    type Guard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self;
+
+    fn lock(&self) -> LockResult<Self::Guard<'_>>;
+    fn try_lock(&self) -> TryLockResult<Self::Guard<'_>>;
+}
+
+/// The default [`RawRwLock`] backend, built on `std::sync::RwLock`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StdRwLock<T>(std::sync::RwLock<T>);
+
+#[cfg(feature = "std")]
+impl<T> RawRwLock<T> for StdRwLock<T> {
+    type ReadGuard<'a>
+        = std::sync::RwLockReadGuard<'a, T>
+    where
+        T: 'a;
+    type WriteGuard<'a>
+        = std::sync::RwLockWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn new(value: T) -> Self {
+        Self(std::sync::RwLock::new(value))
+    }
+
+    fn read(&self) -> LockResult<Self::ReadGuard<'_>> {
+        self.0.read()
+    }
+
+    fn write(&self) -> LockResult<Self::WriteGuard<'_>> {
+        self.0.write()
+    }
+
+    fn try_read(&self) -> TryLockResult<Self::ReadGuard<'_>> {
+        self.0.try_read()
+    }
+
+    fn try_write(&self) -> TryLockResult<Self::WriteGuard<'_>> {
+        self.0.try_write()
+    }
+}
+
+/// The default [`RawMutex`] backend, built on `std::sync::Mutex`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StdMutex<T>(std::sync::Mutex<T>);
+
+#[cfg(feature = "std")]
+impl<T> RawMutex<T> for StdMutex<T> {
+    type Guard<'a>
+        = std::sync::MutexGuard<'a, T>
+    where
+        T: 'a;
+
+    fn new(value: T) -> Self {
+        Self(std::sync::Mutex::new(value))
+    }
+
+    fn lock(&self) -> LockResult<Self::Guard<'_>> {
+        self.0.lock()
+    }
+
+    fn try_lock(&self) -> TryLockResult<Self::Guard<'_>> {
+        self.0.try_lock()
+    }
+}
+
+/// A [`RawRwLock`] backend built on `spin::RwLock`.
+///
+/// Spinning never poisons, so `read`/`write` always succeed and
+/// `try_read`/`try_write` fail only with `WouldBlock`. See the module docs
+/// for why this isn't a full `no_std` story on its own.
+#[cfg(feature = "spin")]
+#[derive(Debug)]
+pub struct SpinRwLock<T>(spin::RwLock<T>);
+
+#[cfg(feature = "spin")]
+impl<T> RawRwLock<T> for SpinRwLock<T> {
+    type ReadGuard<'a>
+        = spin::RwLockReadGuard<'a, T>
+    where
+        T: 'a;
+    type WriteGuard<'a>
+        = spin::RwLockWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn new(value: T) -> Self {
+        Self(spin::RwLock::new(value))
+    }
+
+    fn read(&self) -> LockResult<Self::ReadGuard<'_>> {
+        Ok(self.0.read())
+    }
+
+    fn write(&self) -> LockResult<Self::WriteGuard<'_>> {
+        Ok(self.0.write())
+    }
+
+    fn try_read(&self) -> TryLockResult<Self::ReadGuard<'_>> {
+        self.0.try_read().ok_or(std::sync::TryLockError::WouldBlock)
+    }
+
+    fn try_write(&self) -> TryLockResult<Self::WriteGuard<'_>> {
+        self.0
+            .try_write()
+            .ok_or(std::sync::TryLockError::WouldBlock)
+    }
+}
+
+/// A [`RawMutex`] backend built on `spin::Mutex`.
+///
+/// Spinning never poisons, so `lock` always succeeds and `try_lock` fails
+/// only with `WouldBlock`. See the module docs for why this isn't a full
+/// `no_std` story on its own.
+#[cfg(feature = "spin")]
+#[derive(Debug)]
+pub struct SpinMutex<T>(spin::Mutex<T>);
+
+#[cfg(feature = "spin")]
+impl<T> RawMutex<T> for SpinMutex<T> {
+    type Guard<'a>
+        = spin::MutexGuard<'a, T>
+    where
+        T: 'a;
+
+    fn new(value: T) -> Self {
+        Self(spin::Mutex::new(value))
+    }
+
+    fn lock(&self) -> LockResult<Self::Guard<'_>> {
+        Ok(self.0.lock())
+    }
+
+    fn try_lock(&self) -> TryLockResult<Self::Guard<'_>> {
+        self.0.try_lock().ok_or(std::sync::TryLockError::WouldBlock)
+    }
+}
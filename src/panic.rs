@@ -0,0 +1,103 @@
+//
+// Copyright 2023 Jacob R. Green
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Panic isolation shared by [`Property`](crate::property::Property) and
+//! [`Event`](crate::event::Event).
+//!
+//! A single panicking callback used to unwind straight through the write
+//! guard's `Drop`/the event's dispatch loop, poisoning the lock and
+//! silencing every callback after it. Each callback is now invoked through
+//! [`PanicPolicy::invoke`], which catches the panic so the rest of the
+//! dispatch still runs; [`PanicPolicy::finish`] then decides what happens
+//! to the panics that were caught.
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// A panic payload captured from a single callback invocation.
+pub type CallbackPanic = Box<dyn Any + Send + 'static>;
+
+/// What a dispatcher should do with panics captured from hooked callbacks.
+///
+/// Defaults to [`PanicPolicy::SwallowAndLog`].
+#[derive(Clone, Default)]
+pub enum PanicPolicy {
+    /// Log each panic to stderr and otherwise ignore it.
+    #[default]
+    SwallowAndLog,
+    /// Run every remaining callback, then resume unwinding with the first
+    /// captured panic once dispatch has finished.
+    ResumeUnwindAfterAll,
+    /// Forward every captured panic payload to a user-supplied sink instead
+    /// of swallowing or unwinding.
+    Forward(Arc<dyn Fn(CallbackPanic) + Send + Sync>),
+}
+
+// Manual impl rather than `#[derive(Debug)]`: `Forward`'s `Arc<dyn Fn(..)>`
+// sink has no `Debug` impl, so the derive would reject the whole enum.
+impl fmt::Debug for PanicPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanicPolicy::SwallowAndLog => f.write_str("SwallowAndLog"),
+            PanicPolicy::ResumeUnwindAfterAll => f.write_str("ResumeUnwindAfterAll"),
+            PanicPolicy::Forward(_) => f.write_str("Forward(..)"),
+        }
+    }
+}
+
+impl PanicPolicy {
+    /// Invokes `f`, catching any panic so it cannot unwind into the caller.
+    pub(crate) fn invoke(&self, f: impl FnOnce()) -> Option<CallbackPanic> {
+        panic::catch_unwind(AssertUnwindSafe(f)).err()
+    }
+
+    /// Applies this policy to the panics captured during one dispatch pass.
+    pub(crate) fn finish(&self, panics: Vec<CallbackPanic>) {
+        match self {
+            PanicPolicy::SwallowAndLog => {
+                for panic in &panics {
+                    log_panic(panic);
+                }
+            }
+            PanicPolicy::ResumeUnwindAfterAll => {
+                let mut panics = panics.into_iter();
+                if let Some(first) = panics.next() {
+                    for panic in panics {
+                        log_panic(&panic);
+                    }
+                    panic::resume_unwind(first);
+                }
+            }
+            PanicPolicy::Forward(sink) => {
+                for panic in panics {
+                    sink(panic);
+                }
+            }
+        }
+    }
+}
+
+fn log_panic(payload: &CallbackPanic) {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        eprintln!("eventify: callback panicked: {message}");
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        eprintln!("eventify: callback panicked: {message}");
+    } else {
+        eprintln!("eventify: callback panicked");
+    }
+}
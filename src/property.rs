@@ -14,106 +14,290 @@
 // limitations under the License.
 //
 
+use crate::dispatch::DispatchLock;
+use crate::lock::{RawMutex, RawRwLock};
+#[cfg(all(feature = "spin", not(feature = "std")))]
+use crate::lock::{SpinMutex, SpinRwLock};
+#[cfg(feature = "std")]
+use crate::lock::{StdMutex, StdRwLock};
+use crate::panic::PanicPolicy;
 use std::cell::UnsafeCell;
+use std::marker::PhantomData;
 use std::sync::*;
 
+type Callback<T> = Arc<UnsafeCell<dyn FnMut(&T)>>;
+
+// `pub` (rather than private) because it appears in the trait bounds of
+// public types like `Property`/`PropertyWriteGuard`/`ReadonlyBinding` via
+// `L: RawRwLock<PropertyData<T>>` — a private type there would be
+// unreachable from outside the crate despite being part of a public
+// signature. Its fields stay private; nothing outside this module can
+// construct or inspect one.
+// `value` is an `Arc<T>` rather than a bare `T` so that snapshotting it for
+// deferred dispatch (see `snapshot`/`snapshot_excluding` below) is an O(1)
+// pointer clone regardless of `T`'s size, and needs no `T: Clone` bound at
+// all. Only actually mutating the value in place (`PropertyWriteGuard::
+// get_mut`) needs `T: Clone`, as a copy-on-write fallback for the rare case
+// where a previous write's dispatch snapshot is still holding a clone of
+// this same `Arc` when a new write comes in.
 #[derive(Debug)]
-struct PropertyData<T> {
-    value: T,
-    callbacks: Vec<Arc<UnsafeCell<dyn FnMut(&T) -> ()>>>,
+pub struct PropertyData<T> {
+    value: Arc<T>,
+    callbacks: Vec<Callback<T>>,
+    panic_policy: PanicPolicy,
 }
 
 impl<T> PropertyData<T> {
     fn new(value: T) -> Self {
         Self {
-            value,
+            value: Arc::new(value),
             callbacks: Default::default(),
+            panic_policy: Default::default(),
         }
     }
 
-    fn invoke_all_callbacks(&mut self) {
-        for callback in &mut self.callbacks {
-            unsafe {
-                (*callback.get())(&self.value);
-            }
-        }
+    fn add_callback(&mut self, callback: Callback<T>) {
+        self.callbacks.push(callback);
     }
 
-    fn invoke_excluding(&mut self, excluded: &Arc<UnsafeCell<dyn FnMut(&T) -> ()>>) {
-        for callback in &mut self.callbacks {
-            if !Arc::ptr_eq(excluded, callback) {
-                unsafe {
-                    (*callback.get())(&self.value);
-                }
-            }
+    fn remove_callback(&mut self, callback: &Callback<T>) {
+        self.callbacks.retain(|c| !Arc::ptr_eq(callback, c));
+    }
+
+    /// Snapshots the value and callback list so they can be dispatched
+    /// after the lock guarding this data has been released, instead of
+    /// while it's still held.
+    ///
+    /// Returns `None` if there are no callbacks to notify, so a write to a
+    /// property nobody is watching never pays for this at all.
+    fn snapshot(&self) -> Option<PropertyDispatch<T>> {
+        if self.callbacks.is_empty() {
+            return None;
         }
+        Some(PropertyDispatch {
+            value: self.value.clone(),
+            callbacks: self.callbacks.clone(),
+            panic_policy: self.panic_policy.clone(),
+        })
     }
 
-    fn add_callback(&mut self, callback: Arc<UnsafeCell<dyn FnMut(&T) -> ()>>) {
-        self.callbacks.push(callback);
+    /// Same as [`Self::snapshot`], but omits `excluded` from the callback
+    /// list.
+    fn snapshot_excluding(&self, excluded: &Callback<T>) -> Option<PropertyDispatch<T>> {
+        let callbacks: Vec<_> = self
+            .callbacks
+            .iter()
+            .filter(|c| !Arc::ptr_eq(excluded, c))
+            .cloned()
+            .collect();
+        if callbacks.is_empty() {
+            return None;
+        }
+        Some(PropertyDispatch {
+            value: self.value.clone(),
+            callbacks,
+            panic_policy: self.panic_policy.clone(),
+        })
     }
+}
 
-    fn remove_callback(&mut self, callback: &Arc<UnsafeCell<dyn FnMut(&T) -> ()>>) {
-        self.callbacks.retain(|c| !Arc::ptr_eq(callback, c));
+/// A snapshot of a property's value and callback list, dispatched once the
+/// property's lock has been released so a callback may safely read or
+/// write the same property without deadlocking.
+struct PropertyDispatch<T> {
+    value: Arc<T>,
+    callbacks: Vec<Callback<T>>,
+    panic_policy: PanicPolicy,
+}
+
+impl<T> PropertyDispatch<T> {
+    fn run(self) {
+        let mut panics = Vec::new();
+        for callback in &self.callbacks {
+            let panic = self
+                .panic_policy
+                .invoke(|| unsafe { (*callback.get())(&self.value) });
+            panics.extend(panic);
+        }
+        self.panic_policy.finish(panics);
     }
 }
 
 /// A thread-safe property that can be hooked into for changed events.
+///
+/// Generic over the lock backend `L` used for the value and `M` used for
+/// the upgradable-read token. The `std` feature (on by default) selects
+/// [`StdRwLock`]/[`StdMutex`], matching the original `std::sync`-backed
+/// behavior; with `std` disabled, the `spin` feature selects spin-based
+/// backends instead (see [`crate::lock`] for why this doesn't make the
+/// crate `no_std`). Existing
+/// code that names `Property<T>` keeps compiling unchanged against
+/// whichever backend feature is active.
 #[derive(Debug)]
-pub struct Property<T> {
-    inner: Arc<RwLock<PropertyData<T>>>,
+pub struct Property<
+    T,
+    #[cfg(feature = "std")] L: RawRwLock<PropertyData<T>> = StdRwLock<PropertyData<T>>,
+    #[cfg(all(feature = "spin", not(feature = "std")))] L: RawRwLock<PropertyData<T>> =
+        SpinRwLock<PropertyData<T>>,
+    #[cfg(not(any(feature = "std", feature = "spin")))] L: RawRwLock<PropertyData<T>>,
+    #[cfg(feature = "std")] M: RawMutex<()> = StdMutex<()>,
+    #[cfg(all(feature = "spin", not(feature = "std")))] M: RawMutex<()> = SpinMutex<()>,
+    #[cfg(not(any(feature = "std", feature = "spin")))] M: RawMutex<()>,
+> {
+    inner: Arc<L>,
+    upgrade_lock: M,
+    dispatch_lock: Arc<DispatchLock>,
+    _marker: PhantomData<T>,
 }
 
-impl<T> Property<T> {
+impl<T, L, M> Property<T, L, M>
+where
+    L: RawRwLock<PropertyData<T>>,
+    M: RawMutex<()>,
+{
     pub fn new(value: T) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(PropertyData::new(value))),
+            inner: Arc::new(L::new(PropertyData::new(value))),
+            upgrade_lock: M::new(()),
+            dispatch_lock: Arc::new(DispatchLock::default()),
+            _marker: PhantomData,
         }
     }
 
     /// Locks the property and returns a guard that can be used to read the value.
-    pub fn read(&self) -> LockResult<PropertyReadGuard<'_, T>> {
-        map_lock_result(self.inner.read(), |inner| PropertyReadGuard::from(inner))
+    pub fn read(&self) -> LockResult<PropertyReadGuard<'_, T, L>> {
+        map_lock_result(self.inner.read(), PropertyReadGuard::from_guard)
+    }
+
+    /// Attempts to lock the property for shared access without blocking.
+    pub fn try_read(&self) -> TryLockResult<PropertyReadGuard<'_, T, L>> {
+        map_try_lock_result(self.inner.try_read(), PropertyReadGuard::from_guard)
+    }
+
+    /// Locks the property for shared access, with the option to later upgrade
+    /// to exclusive access via [`PropertyUpgradableGuard::upgrade`].
+    ///
+    /// At most one upgradable guard may be held at a time, so a caller that
+    /// inspects the value and decides to mutate it can upgrade without the
+    /// read-then-write gap that `read()` followed by `write()` would leave
+    /// open for another thread to change the value in between.
+    pub fn upgradable_read(&self) -> LockResult<PropertyUpgradableGuard<'_, T, L, M>> {
+        let upgrade_token = match self.upgrade_lock.lock() {
+            Ok(token) => token,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        match self.inner.read() {
+            Ok(inner) => Ok(PropertyUpgradableGuard {
+                inner,
+                upgrade_token,
+                property: self,
+            }),
+            Err(poisoned) => Err(PoisonError::new(PropertyUpgradableGuard {
+                inner: poisoned.into_inner(),
+                upgrade_token,
+                property: self,
+            })),
+        }
     }
 
-    /// Locks the property and returns a guard that can be used to read and write the value.
-    pub fn write(&self) -> LockResult<PropertyWriteGuard<T>> {
-        map_lock_result(self.inner.write(), |inner| PropertyWriteGuard::from(inner))
+    /// Sets how panicking callbacks are handled when the property's
+    /// changed event fires.
+    ///
+    /// Defaults to [`PanicPolicy::SwallowAndLog`].
+    pub fn set_panic_policy(&self, policy: PanicPolicy) {
+        self.inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .panic_policy = policy;
     }
 
     #[must_use]
     #[inline(always)]
-    pub fn bind(&self, f: impl FnMut(&T) + 'static) -> ReadonlyBinding<T> {
+    pub fn bind(&self, f: impl FnMut(&T) + 'static) -> ReadonlyBinding<T, L> {
         self.bind_internal(box_callback(f))
     }
 
     #[must_use]
     #[inline(always)]
-    pub fn bind_mut(&self, f: impl FnMut(&T) + 'static) -> ReadWriteBinding<T> {
+    pub fn bind_mut(&self, f: impl FnMut(&T) + 'static) -> ReadWriteBinding<T, L> {
         self.bind_mut_internal(box_callback(f))
     }
 
-    fn bind_internal(&self, f: Arc<UnsafeCell<dyn FnMut(&T) -> ()>>) -> ReadonlyBinding<T> {
-        self.inner.write().unwrap().add_callback(f.clone());
+    fn bind_internal(&self, f: Callback<T>) -> ReadonlyBinding<T, L> {
+        self.inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .add_callback(f.clone());
         ReadonlyBinding {
             inner: Some(BindingData {
                 property: self.inner.clone(),
+                dispatch_lock: self.dispatch_lock.clone(),
                 callback: f,
             }),
         }
     }
 
-    fn bind_mut_internal(&self, f: Arc<UnsafeCell<dyn FnMut(&T) -> ()>>) -> ReadWriteBinding<T> {
-        self.inner.write().unwrap().add_callback(f.clone());
+    fn bind_mut_internal(&self, f: Callback<T>) -> ReadWriteBinding<T, L> {
+        self.inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .add_callback(f.clone());
         ReadWriteBinding {
             inner: Some(BindingData {
                 property: self.inner.clone(),
+                dispatch_lock: self.dispatch_lock.clone(),
                 callback: f,
             }),
         }
     }
 }
 
+impl<T, L, M> Property<T, L, M>
+where
+    L: RawRwLock<PropertyData<T>>,
+    M: RawMutex<()>,
+{
+    /// Locks the property and returns a guard that can be used to read and write the value.
+    ///
+    /// Briefly takes the same upgrade token as
+    /// [`Property::upgradable_read`]/[`PropertyUpgradableGuard::upgrade`]
+    /// while acquiring the data lock, and releases it again immediately.
+    /// Without this, a plain write could commit in the gap between an
+    /// upgradable reader releasing its read lock and acquiring the write
+    /// lock, defeating the whole point of upgrading instead of re-reading.
+    pub fn write(&self) -> LockResult<PropertyWriteGuard<'_, T, L>> {
+        let upgrade_token = match self.upgrade_lock.lock() {
+            Ok(token) => token,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let result = map_lock_result(self.inner.write(), |inner| {
+            PropertyWriteGuard::from_guard(inner, self.dispatch_lock.clone())
+        });
+        drop(upgrade_token);
+        result
+    }
+
+    /// Attempts to lock the property for exclusive access without blocking.
+    ///
+    /// Takes the upgrade token non-blockingly too, for the same reason as
+    /// [`Property::write`] — so this also reports [`TryLockError::WouldBlock`]
+    /// while an [`Property::upgradable_read`] is in progress, rather than
+    /// racing it.
+    pub fn try_write(&self) -> TryLockResult<PropertyWriteGuard<'_, T, L>> {
+        let upgrade_token = match self.upgrade_lock.try_lock() {
+            Ok(token) => token,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return Err(TryLockError::WouldBlock),
+        };
+        let result = map_try_lock_result(self.inner.try_write(), |inner| {
+            PropertyWriteGuard::from_guard(inner, self.dispatch_lock.clone())
+        });
+        drop(upgrade_token);
+        result
+    }
+}
+
 #[inline(always)]
 fn map_lock_result<T, U, F>(result: LockResult<T>, f: F) -> LockResult<U>
 where
@@ -124,182 +308,425 @@ where
         .map_err(|e| PoisonError::new(f(e.into_inner())))
 }
 
+#[inline(always)]
+fn map_try_lock_result<T, U, F>(result: TryLockResult<T>, f: F) -> TryLockResult<U>
+where
+    F: Fn(T) -> U,
+{
+    result.map(&f).map_err(|e| match e {
+        TryLockError::Poisoned(e) => TryLockError::Poisoned(PoisonError::new(f(e.into_inner()))),
+        TryLockError::WouldBlock => TryLockError::WouldBlock,
+    })
+}
+
 /// A guard that can be used to read the value of a property.
 ///
 /// This does not invoke the changed event when dropped.
-pub struct PropertyReadGuard<'a, T> {
-    inner: RwLockReadGuard<'a, PropertyData<T>>,
+pub struct PropertyReadGuard<'a, T, L: RawRwLock<PropertyData<T>> + 'a> {
+    inner: L::ReadGuard<'a>,
 }
 
-impl<'a, T> PropertyReadGuard<'a, T> {
+impl<'a, T, L: RawRwLock<PropertyData<T>>> PropertyReadGuard<'a, T, L> {
     pub fn get(&self) -> &T {
         &self.inner.value
     }
-}
 
-impl<'a, T> From<RwLockReadGuard<'a, PropertyData<T>>> for PropertyReadGuard<'a, T> {
-    fn from(inner: RwLockReadGuard<'a, PropertyData<T>>) -> Self {
+    // An inherent constructor rather than `impl From<L::ReadGuard<'a>>`:
+    // `L::ReadGuard<'a>` is a GAT, so nothing stops a backend from picking
+    // `ReadGuard<'a> = PropertyReadGuard<'a, T, L>`, which would conflict
+    // with the standard library's blanket `impl<T> From<T> for T`.
+    fn from_guard(inner: L::ReadGuard<'a>) -> Self {
         Self { inner }
     }
 }
 
-impl<'a, T> From<RwLockWriteGuard<'a, PropertyData<T>>> for PropertyWriteGuard<'a, T> {
-    fn from(inner: RwLockWriteGuard<'a, PropertyData<T>>) -> Self {
-        Self { inner }
+/// A guard that can be used to read the value of a property, with the
+/// option to upgrade to an exclusive [`PropertyWriteGuard`].
+///
+/// This does not invoke the changed event when dropped.
+pub struct PropertyUpgradableGuard<'a, T, L: RawRwLock<PropertyData<T>> + 'a, M: RawMutex<()> + 'a>
+{
+    inner: L::ReadGuard<'a>,
+    upgrade_token: M::Guard<'a>,
+    property: &'a Property<T, L, M>,
+}
+
+impl<'a, T, L: RawRwLock<PropertyData<T>>, M: RawMutex<()>> PropertyUpgradableGuard<'a, T, L, M> {
+    pub fn get(&self) -> &T {
+        &self.inner.value
+    }
+}
+
+impl<'a, T, L: RawRwLock<PropertyData<T>>, M: RawMutex<()>> PropertyUpgradableGuard<'a, T, L, M> {
+    /// Converts this guard into an exclusive [`PropertyWriteGuard`].
+    ///
+    /// The upgrade token is held until the exclusive lock has been
+    /// acquired, so no other upgradable reader can be created while this
+    /// upgrade is in progress.
+    pub fn upgrade(self) -> PropertyWriteGuard<'a, T, L> {
+        drop(self.inner);
+        let inner = self
+            .property
+            .inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        drop(self.upgrade_token);
+        PropertyWriteGuard::from_guard(inner, self.property.dispatch_lock.clone())
     }
 }
 
 /// A guard that can be used to read and write a property.
 ///
-/// When dropped, the property's changed event is invoked.
-pub struct PropertyWriteGuard<'a, T> {
-    inner: RwLockWriteGuard<'a, PropertyData<T>>,
+/// When dropped, the property's lock is released and only then is the
+/// property's changed event invoked, with a snapshot of the committed
+/// value and callback list taken just before release. This lets a
+/// callback safely read or write the same property it was triggered by,
+/// which would otherwise deadlock while the write lock was still held
+/// (`std::sync::RwLock` does not support recursive acquisition). A hook
+/// removed concurrently with an in-flight dispatch may still observe one
+/// final invocation, since it was already part of the dispatched snapshot.
+///
+/// Dispatch itself is serialized through the property's [`DispatchLock`],
+/// entered after the data lock is released: otherwise two writers on
+/// separate threads could release the data lock and run their dispatch
+/// loops at the same time, each invoking the same callback concurrently.
+pub struct PropertyWriteGuard<'a, T, L: RawRwLock<PropertyData<T>> + 'a> {
+    inner: Option<L::WriteGuard<'a>>,
+    dispatch_lock: Arc<DispatchLock>,
 }
 
-impl<'a, T> PropertyWriteGuard<'a, T> {
+impl<'a, T, L: RawRwLock<PropertyData<T>>> PropertyWriteGuard<'a, T, L> {
     pub fn get(&self) -> &T {
-        &self.inner.value
+        &self.inner.as_ref().unwrap().value
     }
 
+    // An inherent constructor rather than `impl From<L::WriteGuard<'a>>`,
+    // for the same reason as `PropertyReadGuard::from_guard`.
+    fn from_guard(inner: L::WriteGuard<'a>, dispatch_lock: Arc<DispatchLock>) -> Self {
+        Self {
+            inner: Some(inner),
+            dispatch_lock,
+        }
+    }
+}
+
+impl<'a, T: Clone, L: RawRwLock<PropertyData<T>>> PropertyWriteGuard<'a, T, L> {
+    /// Returns a mutable reference to the value.
+    ///
+    /// The value is stored as an `Arc<T>` so that dispatching the changed
+    /// event after this guard is dropped (see the guard's docs) only ever
+    /// clones a pointer, not `T` itself. Obtaining `&mut T` is the one place
+    /// that can still need to clone `T`: if a previous write's dispatch
+    /// snapshot is still holding a reference to this same `Arc` (the
+    /// reentrant-write case), [`Arc::make_mut`] falls back to cloning the
+    /// value so this guard gets its own exclusive copy to mutate.
     pub fn get_mut(&mut self) -> &mut T {
-        &mut self.inner.value
+        Arc::make_mut(&mut self.inner.as_mut().unwrap().value)
     }
 }
 
-impl<T> Drop for PropertyWriteGuard<'_, T> {
+impl<T, L: RawRwLock<PropertyData<T>>> Drop for PropertyWriteGuard<'_, T, L> {
     fn drop(&mut self) {
-        self.inner.invoke_all_callbacks();
+        if let Some(inner) = self.inner.take() {
+            let dispatch = inner.snapshot();
+            drop(inner);
+            if let Some(dispatch) = dispatch {
+                let _guard = self.dispatch_lock.enter();
+                dispatch.run();
+            }
+        }
     }
 }
 
-fn box_callback<T>(f: impl FnMut(&T) + 'static) -> Arc<UnsafeCell<dyn FnMut(&T) -> ()>> {
-    unimplemented!()
+fn box_callback<T>(f: impl FnMut(&T) + 'static) -> Callback<T> {
+    Arc::new(UnsafeCell::new(f))
 }
 
-impl<T> Default for Property<T>
+impl<T, L, M> Default for Property<T, L, M>
 where
     T: Default,
+    L: RawRwLock<PropertyData<T>>,
+    M: RawMutex<()>,
 {
     fn default() -> Self {
         Self::new(Default::default())
     }
 }
 
-struct BindingData<T> {
-    property: Arc<RwLock<PropertyData<T>>>,
-    callback: Arc<UnsafeCell<dyn FnMut(&T) -> ()>>,
+#[derive(Debug)]
+struct BindingData<T, L: RawRwLock<PropertyData<T>>> {
+    property: Arc<L>,
+    dispatch_lock: Arc<DispatchLock>,
+    callback: Callback<T>,
 }
 
-impl<T> BindingData<T> {
+impl<T, L: RawRwLock<PropertyData<T>>> BindingData<T, L> {
     fn unbind(&self) {
         self.property
             .write()
-            .unwrap()
+            .unwrap_or_else(PoisonError::into_inner)
             .remove_callback(&self.callback);
     }
 }
 
 /// A readonly binding to a property.
 #[derive(Debug)]
-pub struct ReadonlyBinding<T> {
-    inner: Option<BindingData<T>>,
+pub struct ReadonlyBinding<T, L: RawRwLock<PropertyData<T>>> {
+    inner: Option<BindingData<T, L>>,
 }
 
-impl<T> ReadonlyBinding<T> {
-    fn leak(mut self) {
+impl<T, L: RawRwLock<PropertyData<T>>> ReadonlyBinding<T, L> {
+    pub fn leak(mut self) {
         self.inner.take();
     }
 
-    fn read(&self) -> LockResult<BindingReadGuard<'_, T>> {
+    pub fn read(&self) -> LockResult<BindingReadGuard<'_, T, L>> {
         map_lock_result(self.inner.as_ref().unwrap().property.read(), |inner| {
-            BindingReadGuard::from(inner)
+            BindingReadGuard::from_guard(inner)
+        })
+    }
+
+    pub fn try_read(&self) -> TryLockResult<BindingReadGuard<'_, T, L>> {
+        map_try_lock_result(self.inner.as_ref().unwrap().property.try_read(), |inner| {
+            BindingReadGuard::from_guard(inner)
         })
     }
 }
 
-impl Drop for ReadonlyBinding<()> {
+impl<T, L: RawRwLock<PropertyData<T>>> Drop for ReadonlyBinding<T, L> {
     fn drop(&mut self) {
-        self.inner.as_ref().map(|data| {
+        if let Some(data) = self.inner.as_ref() {
             data.unbind();
-        });
+        }
     }
 }
 
 /// A read-write binding to a property.
 #[derive(Debug)]
-pub struct ReadWriteBinding<T> {
-    inner: Option<BindingData<T>>,
+pub struct ReadWriteBinding<T, L: RawRwLock<PropertyData<T>>> {
+    inner: Option<BindingData<T, L>>,
 }
 
-impl<T> ReadWriteBinding<T> {
-    fn leak(mut self) {
+impl<T, L: RawRwLock<PropertyData<T>>> ReadWriteBinding<T, L> {
+    pub fn leak(mut self) {
         self.inner.take();
     }
 
-    fn read(&self) -> LockResult<BindingReadGuard<'_, T>> {
+    pub fn read(&self) -> LockResult<BindingReadGuard<'_, T, L>> {
         map_lock_result(self.inner.as_ref().unwrap().property.read(), |inner| {
-            BindingReadGuard::from(inner)
+            BindingReadGuard::from_guard(inner)
         })
     }
 
-    fn write(&self) -> LockResult<BindingWriteGuard<T>> {
+    pub fn try_read(&self) -> TryLockResult<BindingReadGuard<'_, T, L>> {
+        map_try_lock_result(self.inner.as_ref().unwrap().property.try_read(), |inner| {
+            BindingReadGuard::from_guard(inner)
+        })
+    }
+}
+
+impl<T, L: RawRwLock<PropertyData<T>>> ReadWriteBinding<T, L> {
+    pub fn write(&self) -> LockResult<BindingWriteGuard<'_, T, L>> {
         map_lock_result(self.inner.as_ref().unwrap().property.write(), |inner| {
             BindingWriteGuard::new(inner, self.inner.as_ref().unwrap())
         })
     }
+
+    pub fn try_write(&self) -> TryLockResult<BindingWriteGuard<'_, T, L>> {
+        map_try_lock_result(self.inner.as_ref().unwrap().property.try_write(), |inner| {
+            BindingWriteGuard::new(inner, self.inner.as_ref().unwrap())
+        })
+    }
 }
 
-impl Drop for ReadWriteBinding<()> {
+impl<T, L: RawRwLock<PropertyData<T>>> Drop for ReadWriteBinding<T, L> {
     fn drop(&mut self) {
-        self.inner.as_ref().map(|data| {
+        if let Some(data) = self.inner.as_ref() {
             data.unbind();
-        });
+        }
     }
 }
 
 /// A guard that can be used to read the value of a property.
 ///
 /// This does not invoke the changed event when dropped.
-pub struct BindingReadGuard<'a, T> {
-    inner: RwLockReadGuard<'a, PropertyData<T>>,
+pub struct BindingReadGuard<'a, T, L: RawRwLock<PropertyData<T>> + 'a> {
+    inner: L::ReadGuard<'a>,
 }
 
-impl<'a, T> BindingReadGuard<'a, T> {
+impl<'a, T, L: RawRwLock<PropertyData<T>>> BindingReadGuard<'a, T, L> {
     pub fn get(&self) -> &T {
         &self.inner.value
     }
-}
 
-impl<'a, T> From<RwLockReadGuard<'a, PropertyData<T>>> for BindingReadGuard<'a, T> {
-    fn from(inner: RwLockReadGuard<'a, PropertyData<T>>) -> Self {
+    // An inherent constructor rather than `impl From<L::ReadGuard<'a>>`,
+    // for the same reason as `PropertyReadGuard::from_guard`.
+    fn from_guard(inner: L::ReadGuard<'a>) -> Self {
         Self { inner }
     }
 }
 
 /// A guard that can be used to read and write a property.
 ///
-/// When dropped, the property's changed event is invoked for every binding except itself.
-pub struct BindingWriteGuard<'a, T> {
-    inner: RwLockWriteGuard<'a, PropertyData<T>>,
-    data: &'a BindingData<T>,
+/// When dropped, the property's lock is released and only then is the
+/// property's changed event invoked for every binding except itself, the
+/// same deferred-dispatch behavior as [`PropertyWriteGuard`].
+pub struct BindingWriteGuard<'a, T, L: RawRwLock<PropertyData<T>> + 'a> {
+    inner: Option<L::WriteGuard<'a>>,
+    data: &'a BindingData<T, L>,
 }
 
-impl<'a, T> BindingWriteGuard<'a, T> {
-    fn new(inner: RwLockWriteGuard<'a, PropertyData<T>>, data: &'a BindingData<T>) -> Self {
-        Self { inner, data }
+impl<'a, T, L: RawRwLock<PropertyData<T>>> BindingWriteGuard<'a, T, L> {
+    fn new(inner: L::WriteGuard<'a>, data: &'a BindingData<T, L>) -> Self {
+        Self {
+            inner: Some(inner),
+            data,
+        }
     }
 
-    pub fn get(&self) -> &() {
-        &self.inner.value
+    pub fn get(&self) -> &T {
+        &self.inner.as_ref().unwrap().value
     }
+}
 
-    pub fn get_mut(&mut self) -> &mut () {
-        &mut self.inner.value
+impl<'a, T: Clone, L: RawRwLock<PropertyData<T>>> BindingWriteGuard<'a, T, L> {
+    /// Returns a mutable reference to the value.
+    ///
+    /// See [`PropertyWriteGuard::get_mut`] for why this needs `T: Clone`
+    /// while the rest of this guard doesn't.
+    pub fn get_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.inner.as_mut().unwrap().value)
     }
 }
 
-impl<T> Drop for BindingWriteGuard<'_, T> {
+impl<T, L: RawRwLock<PropertyData<T>>> Drop for BindingWriteGuard<'_, T, L> {
     fn drop(&mut self) {
-        self.inner.invoke_excluding(&self.data.callback);
+        if let Some(inner) = self.inner.take() {
+            let dispatch = inner.snapshot_excluding(&self.data.callback);
+            drop(inner);
+            if let Some(dispatch) = dispatch {
+                let _guard = self.data.dispatch_lock.enter();
+                dispatch.run();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panicking_callback_does_not_poison_subsequent_writes() {
+        let property = Property::<i32>::new(0);
+        let _binding = property.bind(|_| panic!("callback panic"));
+
+        {
+            let mut guard = property.write().expect("write should not be poisoned");
+            *guard.get_mut() = 1;
+        }
+
+        assert_eq!(
+            *property.read().expect("lock should not be poisoned").get(),
+            1
+        );
+        assert!(property.write().is_ok());
+    }
+
+    #[test]
+    fn reentrant_write_from_callback_does_not_deadlock() {
+        // `Rc`, not `Arc`: this only needs `'static` ownership for the
+        // same-thread reentrant callback below, never to cross threads.
+        let property = std::rc::Rc::new(Property::<i32>::new(0));
+
+        let reentrant = property.clone();
+        let _binding = property.bind(move |value| {
+            if *value < 3 {
+                *reentrant.write().unwrap().get_mut() += 1;
+            }
+        });
+
+        *property.write().unwrap().get_mut() = 1;
+
+        assert_eq!(*property.read().unwrap().get(), 3);
+    }
+
+    #[test]
+    fn writer_cannot_commit_between_upgradable_read_and_upgrade() {
+        // `Property` isn't `Send`/`Sync` yet (its callback list sits behind
+        // a bare `UnsafeCell`), but this test never touches the property
+        // except through its own locking, and `thread::scope` guarantees
+        // the spawned thread is joined before `property` goes out of
+        // scope, so sharing a reference across that boundary is sound.
+        struct AssertSync<T>(T);
+        unsafe impl<T> Sync for AssertSync<T> {}
+        impl<T> AssertSync<T> {
+            // A method, not a field access: capturing `property.get()` in
+            // the closure below forces the whole `AssertSync` wrapper (and
+            // its `Sync` impl) into the capture, rather than disjoint
+            // closure capture narrowing it straight down to the
+            // non-`Sync` `Property` field inside.
+            fn get(&self) -> &T {
+                &self.0
+            }
+        }
+
+        let property = AssertSync(Property::<i32>::new(0));
+
+        let guard = property.get().upgradable_read().unwrap();
+        assert_eq!(*guard.get(), 0);
+
+        let property = &property;
+
+        std::thread::scope(|scope| {
+            let (started_tx, started_rx) = std::sync::mpsc::channel();
+            let (done_tx, done_rx) = std::sync::mpsc::channel();
+
+            scope.spawn(move || {
+                started_tx.send(()).unwrap();
+                *property.get().write().unwrap().get_mut() = 100;
+                done_tx.send(()).unwrap();
+            });
+
+            started_rx.recv().unwrap();
+            // The writer is now racing to acquire the lock; it must not be
+            // able to commit while this upgradable read — and its eventual
+            // upgrade — is still outstanding. `upgrade_lock` is what closes
+            // that gap; without it this would be a flaky race instead of a
+            // guaranteed block.
+            assert!(matches!(
+                done_rx.recv_timeout(std::time::Duration::from_millis(100)),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+            ));
+
+            let mut write_guard = guard.upgrade();
+            *write_guard.get_mut() = 1;
+            drop(write_guard);
+
+            // Only now that the upgrade has committed is the writer free to
+            // run.
+            done_rx
+                .recv_timeout(std::time::Duration::from_secs(1))
+                .expect("writer should unblock once the upgrade finishes");
+        });
+
+        assert_eq!(*property.get().read().unwrap().get(), 100);
+    }
+
+    #[test]
+    fn try_write_returns_would_block_while_a_reader_holds_the_lock() {
+        let property = Property::<i32>::new(0);
+        let _read_guard = property.read().unwrap();
+        assert!(matches!(
+            property.try_write(),
+            Err(TryLockError::WouldBlock)
+        ));
+    }
+
+    #[test]
+    fn try_read_returns_would_block_while_a_writer_holds_the_lock() {
+        let property = Property::<i32>::new(0);
+        let _write_guard = property.write().unwrap();
+        assert!(matches!(property.try_read(), Err(TryLockError::WouldBlock)));
     }
 }